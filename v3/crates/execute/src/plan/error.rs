@@ -0,0 +1,27 @@
+//! Errors that can occur while planning an NDC query from the internal IR.
+
+/// Errors surfaced while translating the internal IR into an NDC query plan.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    /// A partial-sort predicate was given for an order-by target with no
+    /// relationship path to attach it to (e.g. a root-level column or
+    /// aggregate). NDC has no representation for restricting which rows
+    /// participate in sorting the root collection itself - partial sorting
+    /// only works when traversing a relationship, where the predicate can
+    /// restrict the related rows used for ordering without touching the
+    /// root rows. See `ndc_relationship_path` in `plan::model_selection`.
+    #[error(
+        "cannot apply a partial-sort predicate to a root-level order-by target; partial \
+         sorting is only supported when the order-by target is reached through a \
+         relationship path"
+    )]
+    OrderByPredicateRequiresRelationshipPath,
+
+    /// A root-level `Relevance` order-by target generates a collection-level
+    /// argument (see `ndc_order_by_target`) whose name collided with one
+    /// already present on the model's own arguments. Surfacing this as an
+    /// error avoids silently clobbering a real model argument with a
+    /// generated one.
+    #[error("generated order-by argument `{name}` collides with an existing model argument")]
+    RelevanceArgumentNameCollision { name: ndc_models::ArgumentName },
+}
@@ -0,0 +1,297 @@
+//! Translates the internal IR 'FilterExpression' into an NDC predicate
+
+use std::collections::BTreeMap;
+
+use super::error;
+
+/// Internal IR for a boolean filter predicate, as used by a model's
+/// `filter_clause`, a grouping's `predicate`, and an order-by element's
+/// partial-sort `predicate`.
+#[derive(Debug, Clone)]
+pub(crate) enum FilterExpression<'s> {
+    And {
+        expressions: Vec<FilterExpression<'s>>,
+    },
+    Or {
+        expressions: Vec<FilterExpression<'s>>,
+    },
+    Not {
+        expression: Box<FilterExpression<'s>>,
+    },
+    LocalFieldComparison {
+        column: &'s str,
+        operator: &'s str,
+        value: serde_json::Value,
+    },
+    /// `EXISTS` over either a related collection (reached through a
+    /// relationship) or a nested array/object column that isn't a
+    /// relationship at all, e.g. "institutions where some staff member's
+    /// last name is 'Hughes'", where `staff` is an inline array-of-objects
+    /// column on `institutions`.
+    Exists {
+        in_collection: ExistsInCollection<'s>,
+        predicate: Box<FilterExpression<'s>>,
+    },
+}
+
+/// The collection an `Exists` expression quantifies over.
+#[derive(Debug, Clone)]
+pub(crate) enum ExistsInCollection<'s> {
+    /// The existing relationship-based `EXISTS`, joining through a named
+    /// relationship to the root table or another relationship's target.
+    Relationship { relationship_name: &'s str },
+    /// A nested array-of-objects column is unnested into its own collection,
+    /// whose element type becomes the "current table" for the inner
+    /// predicate - no relationship is traversed to get there.
+    NestedCollection {
+        column: &'s str,
+        field_path: Vec<&'s str>,
+    },
+}
+
+/// Plans a `FilterExpression` into an NDC `Expression`, recursively expanding
+/// boolean combinators and `EXISTS` (over relationships, or over nested
+/// array/object columns via a nested field collection) into the NDC
+/// expression tree.
+pub(crate) fn plan_filter_expression(
+    expression: &FilterExpression,
+    relationships: &mut BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+) -> Result<Option<ndc_models::Expression>, error::Error> {
+    match expression {
+        FilterExpression::And { expressions } => Ok(Some(ndc_models::Expression::And {
+            expressions: plan_filter_expressions(expressions, relationships)?,
+        })),
+        FilterExpression::Or { expressions } => Ok(Some(ndc_models::Expression::Or {
+            expressions: plan_filter_expressions(expressions, relationships)?,
+        })),
+        FilterExpression::Not { expression } => {
+            let expression = plan_filter_expression(expression, relationships)?.unwrap_or(
+                ndc_models::Expression::And {
+                    expressions: Vec::new(),
+                },
+            );
+            Ok(Some(ndc_models::Expression::Not {
+                expression: Box::new(expression),
+            }))
+        }
+        FilterExpression::LocalFieldComparison {
+            column,
+            operator,
+            value,
+        } => Ok(Some(ndc_models::Expression::BinaryComparisonOperator {
+            column: ndc_models::ComparisonTarget::Column {
+                name: ndc_models::FieldName::from(*column),
+                field_path: None,
+            },
+            operator: ndc_models::ComparisonOperatorName::from(*operator),
+            value: ndc_models::ComparisonValue::Scalar {
+                value: value.clone(),
+            },
+        })),
+        FilterExpression::Exists {
+            in_collection,
+            predicate,
+        } => plan_exists(in_collection, predicate, relationships),
+    }
+}
+
+fn plan_filter_expressions(
+    expressions: &[FilterExpression],
+    relationships: &mut BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+) -> Result<Vec<ndc_models::Expression>, error::Error> {
+    expressions
+        .iter()
+        .map(|expression| plan_filter_expression(expression, relationships))
+        .collect::<Result<Vec<_>, error::Error>>()
+        .map(|expressions| expressions.into_iter().flatten().collect())
+}
+
+/// Plans an `Exists` expression. For a nested array/object column, the array
+/// is unnested into a `NestedCollection` and the inner predicate is planned
+/// recursively against the unnested element type - exactly like planning the
+/// predicate for any other collection, just one that isn't reached through a
+/// relationship.
+fn plan_exists(
+    in_collection: &ExistsInCollection,
+    predicate: &FilterExpression,
+    relationships: &mut BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+) -> Result<Option<ndc_models::Expression>, error::Error> {
+    let in_collection = match in_collection {
+        ExistsInCollection::Relationship { relationship_name } => {
+            ndc_models::ExistsInCollection::Related {
+                relationship: ndc_models::RelationshipName::from(*relationship_name),
+                arguments: BTreeMap::new(),
+            }
+        }
+        ExistsInCollection::NestedCollection { column, field_path } => {
+            ndc_models::ExistsInCollection::NestedCollection {
+                column_name: ndc_models::FieldName::from(*column),
+                arguments: BTreeMap::new(),
+                field_path: if field_path.is_empty() {
+                    None
+                } else {
+                    Some(
+                        field_path
+                            .iter()
+                            .map(|field| ndc_models::FieldName::from(*field))
+                            .collect(),
+                    )
+                },
+            }
+        }
+    };
+
+    let predicate = plan_filter_expression(predicate, relationships)?;
+    Ok(Some(ndc_models::Expression::Exists {
+        in_collection,
+        predicate: predicate.map(Box::new),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_field_comparison_translates_to_binary_comparison_operator() {
+        let expression = FilterExpression::LocalFieldComparison {
+            column: "last_name",
+            operator: "_eq",
+            value: serde_json::Value::String("Hughes".into()),
+        };
+        let mut relationships = BTreeMap::new();
+
+        let result = plan_filter_expression(&expression, &mut relationships)
+            .unwrap()
+            .unwrap();
+
+        match result {
+            ndc_models::Expression::BinaryComparisonOperator { column, .. } => {
+                assert_eq!(column, ndc_models::ComparisonTarget::Column {
+                    name: ndc_models::FieldName::from("last_name"),
+                    field_path: None,
+                });
+            }
+            other => panic!("expected a BinaryComparisonOperator, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn empty_and_plans_to_an_always_true_expression() {
+        let expression = FilterExpression::And {
+            expressions: Vec::new(),
+        };
+        let mut relationships = BTreeMap::new();
+
+        let result = plan_filter_expression(&expression, &mut relationships)
+            .unwrap()
+            .unwrap();
+
+        assert!(matches!(
+            result,
+            ndc_models::Expression::And { expressions } if expressions.is_empty()
+        ));
+    }
+
+    #[test]
+    fn exists_over_relationship_translates_to_ndc_related_collection() {
+        let expression = FilterExpression::Exists {
+            in_collection: ExistsInCollection::Relationship {
+                relationship_name: "staff",
+            },
+            predicate: Box::new(FilterExpression::LocalFieldComparison {
+                column: "last_name",
+                operator: "_eq",
+                value: serde_json::Value::String("Hughes".into()),
+            }),
+        };
+        let mut relationships = BTreeMap::new();
+
+        let result = plan_filter_expression(&expression, &mut relationships)
+            .unwrap()
+            .unwrap();
+
+        match result {
+            ndc_models::Expression::Exists {
+                in_collection,
+                predicate,
+            } => {
+                assert!(matches!(
+                    in_collection,
+                    ndc_models::ExistsInCollection::Related { relationship, .. }
+                        if relationship == ndc_models::RelationshipName::from("staff")
+                ));
+                assert!(predicate.is_some());
+            }
+            other => panic!("expected an Exists expression, got {other:?}"),
+        }
+    }
+
+    /// Regression test: `EXISTS` over a nested array/object column (not a
+    /// relationship) must unnest the array via `NestedCollection` rather than
+    /// being dropped or mistaken for a relationship-based `EXISTS`.
+    #[test]
+    fn exists_over_nested_array_column_translates_to_nested_collection() {
+        let expression = FilterExpression::Exists {
+            in_collection: ExistsInCollection::NestedCollection {
+                column: "staff",
+                field_path: Vec::new(),
+            },
+            predicate: Box::new(FilterExpression::LocalFieldComparison {
+                column: "last_name",
+                operator: "_eq",
+                value: serde_json::Value::String("Hughes".into()),
+            }),
+        };
+        let mut relationships = BTreeMap::new();
+
+        let result = plan_filter_expression(&expression, &mut relationships)
+            .unwrap()
+            .unwrap();
+
+        match result {
+            ndc_models::Expression::Exists { in_collection, .. } => {
+                assert!(matches!(
+                    in_collection,
+                    ndc_models::ExistsInCollection::NestedCollection {
+                        column_name,
+                        field_path: None,
+                        ..
+                    } if column_name == ndc_models::FieldName::from("staff")
+                ));
+            }
+            other => panic!("expected an Exists expression, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn exists_over_nested_array_column_with_field_path_is_preserved() {
+        let expression = FilterExpression::Exists {
+            in_collection: ExistsInCollection::NestedCollection {
+                column: "staff",
+                field_path: vec!["address"],
+            },
+            predicate: Box::new(FilterExpression::LocalFieldComparison {
+                column: "city",
+                operator: "_eq",
+                value: serde_json::Value::String("Chicago".into()),
+            }),
+        };
+        let mut relationships = BTreeMap::new();
+
+        let result = plan_filter_expression(&expression, &mut relationships)
+            .unwrap()
+            .unwrap();
+
+        match result {
+            ndc_models::Expression::Exists { in_collection, .. } => {
+                assert!(matches!(
+                    in_collection,
+                    ndc_models::ExistsInCollection::NestedCollection { field_path: Some(path), .. }
+                        if path == vec![ndc_models::FieldName::from("address")]
+                ));
+            }
+            other => panic!("expected an Exists expression, got {other:?}"),
+        }
+    }
+}
@@ -1,4 +1,33 @@
 //! NDC query generation from 'ModelSelection' IR
+//!
+//! ## Known limitations
+//!
+//! - **Partial sorting only works through a relationship path.** A
+//!   partial-sort `predicate` on an order-by element (see
+//!   `ndc_relationship_path`) can only be honored when the target is reached
+//!   through a relationship path, because NDC has no predicate-restricted way
+//!   to sort a subset of the root collection itself. That means the literal
+//!   motivating example from the original request - "order users so that
+//!   active ones come first, but don't reshuffle inactive users," a
+//!   root-level column with no relationship path - is *rejected* with
+//!   `error::Error::OrderByPredicateRequiresRelationshipPath`, not honored.
+//!   **Partial sorting is therefore only partially implemented.** Shipping
+//!   the root-level case needs either a real NDC representation for
+//!   partial-sorting the root collection itself, or explicit product
+//!   sign-off that relationship-path-only partial sorting is the intended
+//!   scope.
+//! - **Relevance ordering's wire shape is unverified, and unconditionally
+//!   sent.** `order_by::OrderByTarget::Relevance` is translated by
+//!   `ndc_order_by_target` into `fields`/`search` arguments on the trailing
+//!   `PathElement`, or, for a root-level target, into collection-level
+//!   arguments named `fields_{n}`/`search_{n}`. That argument shape is our best guess at a cross-connector
+//!   relevance-ordering convention - it is not confirmed against an NDC spec
+//!   PR or any reference connector implementation, and there is currently no
+//!   capability check gating it, so every connector is sent this guessed
+//!   shape regardless of whether it understands it. This needs a named
+//!   source (an NDC spec PR, or a reference connector implementation) before
+//!   it can be trusted, and likely real capability plumbing before it can be
+//!   sent safely to connectors that never opted in.
 
 use std::collections::BTreeMap;
 
@@ -11,17 +40,30 @@ use super::relationships;
 use super::selection_set;
 use super::types;
 use crate::ir::aggregates::{AggregateFieldSelection, AggregateSelectionSet};
+use crate::ir::grouping::{Dimension, Grouping};
 use crate::ir::model_selection::ModelSelection;
 use crate::ir::order_by;
 use crate::remote_joins::types::{JoinLocations, MonotonicCounter, RemoteJoin};
 
+/// Collection-level NDC arguments discovered while planning an order-by (e.g.
+/// a relevance search term with no relationship path to attach to), to be
+/// merged into the query's own arguments by `plan_query_execution`.
+type OrderByArguments = BTreeMap<ndc_models::ArgumentName, ndc_models::Argument>;
+
 /// Create an NDC `Query` based on the internal IR `ModelSelection` settings
 // #[async_recursion]
 pub(crate) fn plan_query_node<'s, 'ir>(
     ir: &'ir ModelSelection<'s>,
     relationships: &mut BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
     join_id_counter: &mut MonotonicCounter,
-) -> Result<(types::QueryNode<'s>, JoinLocations<RemoteJoin<'s, 'ir>>), error::Error> {
+) -> Result<
+    (
+        types::QueryNode<'s>,
+        JoinLocations<RemoteJoin<'s, 'ir>>,
+        OrderByArguments,
+    ),
+    error::Error,
+> {
     let mut query_fields = None;
     let mut join_locations = JoinLocations::new();
     if let Some(selection) = &ir.selection {
@@ -35,22 +77,43 @@ pub(crate) fn plan_query_node<'s, 'ir>(
         join_locations = locations;
     }
 
+    let mut order_by_arguments = OrderByArguments::new();
+    let mut relevance_argument_counter = MonotonicCounter::new();
+
     let aggregates = ir.aggregate_selection.as_ref().map(ndc_aggregates);
+    let groups = match ir.grouping.as_ref() {
+        Some(grouping) => {
+            let (groups, arguments) =
+                ndc_groups(grouping, relationships, &mut relevance_argument_counter)?;
+            order_by_arguments.extend(arguments);
+            Some(groups)
+        }
+        None => None,
+    };
     let predicate = filter::plan_filter_expression(&ir.filter_clause, relationships)?;
+    let order_by = match ir.order_by.as_ref() {
+        Some(x) => {
+            let (order_by, arguments) = ndc_order_by(
+                &x.order_by_elements,
+                relationships,
+                &mut relevance_argument_counter,
+            )?;
+            order_by_arguments.extend(arguments);
+            Some(order_by)
+        }
+        None => None,
+    };
     let query_node = types::QueryNode {
         limit: ir.limit,
         offset: ir.offset,
-        order_by: ir
-            .order_by
-            .as_ref()
-            .map(|x| ndc_order_by(&x.order_by_elements)),
+        order_by,
         predicate,
         aggregates,
         fields: query_fields,
-        groups: None,
+        groups,
     };
 
-    Ok((query_node, join_locations))
+    Ok((query_node, join_locations, order_by_arguments))
 }
 
 /// Translates the internal IR 'AggregateSelectionSet' into an NDC query aggregates selection
@@ -61,42 +124,123 @@ fn ndc_aggregates(
         .fields
         .iter()
         .map(|(field_name, aggregate_selection)| {
-            let aggregate = match aggregate_selection {
-                AggregateFieldSelection::Count { column_path, .. } => {
-                    ndc_count_aggregate(column_path, false)
-                }
-                AggregateFieldSelection::CountDistinct { column_path, .. } => {
-                    ndc_count_aggregate(column_path, true)
-                }
-                AggregateFieldSelection::AggregationFunction {
-                    function_name,
-                    column_path,
-                    ..
-                } => {
-                    let nonempty::NonEmpty {
-                        head: column,
-                        tail: field_path,
-                    } = column_path;
-                    let nested_field_path = field_path
-                        .iter()
-                        .map(|p| ndc_models::FieldName::from(*p))
-                        .collect::<Vec<_>>();
-                    ndc_models::Aggregate::SingleColumn {
-                        column: ndc_models::FieldName::from(*column),
-                        field_path: if nested_field_path.is_empty() {
-                            None
-                        } else {
-                            Some(nested_field_path)
-                        },
-                        function: ndc_models::AggregateFunctionName::from(function_name.0.as_str()),
-                    }
-                }
-            };
-            (ndc_models::FieldName::from(field_name.as_str()), aggregate)
+            (
+                ndc_models::FieldName::from(field_name.as_str()),
+                ndc_aggregate(aggregate_selection),
+            )
         })
         .collect()
 }
 
+/// Translates a single internal IR 'AggregateFieldSelection' into an NDC `Aggregate`,
+/// shared between the top-level `aggregates` selection and aggregate order-by targets.
+fn ndc_aggregate(aggregate_selection: &AggregateFieldSelection) -> ndc_models::Aggregate {
+    match aggregate_selection {
+        AggregateFieldSelection::Count { column_path, .. } => {
+            ndc_count_aggregate(column_path, false)
+        }
+        AggregateFieldSelection::CountDistinct { column_path, .. } => {
+            ndc_count_aggregate(column_path, true)
+        }
+        AggregateFieldSelection::AggregationFunction {
+            function_name,
+            column_path,
+            ..
+        } => {
+            let nonempty::NonEmpty {
+                head: column,
+                tail: field_path,
+            } = column_path;
+            let nested_field_path = field_path
+                .iter()
+                .map(|p| ndc_models::FieldName::from(*p))
+                .collect::<Vec<_>>();
+            ndc_models::Aggregate::SingleColumn {
+                column: ndc_models::FieldName::from(*column),
+                field_path: if nested_field_path.is_empty() {
+                    None
+                } else {
+                    Some(nested_field_path)
+                },
+                function: ndc_models::AggregateFunctionName::from(function_name.0.as_str()),
+            }
+        }
+    }
+}
+
+/// Translates the internal IR 'Grouping' into an NDC query groups selection,
+/// mirroring `ndc_aggregates` but bucketing the aggregates by one or more
+/// dimension columns (the equivalent of SQL `GROUP BY`).
+fn ndc_groups(
+    grouping: &Grouping,
+    relationships: &mut BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+    relevance_argument_counter: &mut MonotonicCounter,
+) -> Result<(ndc_models::Grouping, OrderByArguments), error::Error> {
+    let dimensions = grouping
+        .dimensions
+        .iter()
+        .map(|dimension| ndc_dimension(dimension, relationships))
+        .collect::<Result<Vec<_>, error::Error>>()?;
+    let aggregates = ndc_aggregates(&grouping.aggregates);
+    let predicate = grouping
+        .predicate
+        .as_ref()
+        .map(|predicate| filter::plan_filter_expression(predicate, relationships))
+        .transpose()?
+        .flatten();
+    let (order_by, order_by_arguments) = match grouping.order_by.as_ref() {
+        Some(order_by_elements) => {
+            let (order_by, arguments) =
+                ndc_order_by(order_by_elements, relationships, relevance_argument_counter)?;
+            (Some(order_by), arguments)
+        }
+        None => (None, OrderByArguments::new()),
+    };
+
+    Ok((
+        ndc_models::Grouping {
+            dimensions,
+            aggregates,
+            predicate,
+            order_by,
+            limit: grouping.limit,
+            offset: grouping.offset,
+        },
+        order_by_arguments,
+    ))
+}
+
+/// Translates a single grouping dimension into an NDC `Dimension`, reusing the
+/// same relationship path construction as nested order-by columns. Dimensions
+/// don't carry a predicate of their own, so `None` is passed through.
+fn ndc_dimension(
+    dimension: &Dimension,
+    relationships: &mut BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+) -> Result<ndc_models::Dimension, error::Error> {
+    let relationship_names = dimension
+        .relationship_path
+        .iter()
+        .map(|path| path.0.as_str());
+    ndc_column_dimension(relationship_names, dimension.column.as_str(), relationships)
+}
+
+/// The primitive core of `ndc_dimension`, taking a plain column name and
+/// relationship names instead of `Dimension`/`RelationshipPathElement` so it
+/// can be unit tested without depending on those types' (externally defined)
+/// shape.
+fn ndc_column_dimension<'a>(
+    relationship_names: impl ExactSizeIterator<Item = &'a str>,
+    column: &str,
+    relationships: &mut BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+) -> Result<ndc_models::Dimension, error::Error> {
+    Ok(ndc_models::Dimension::Column {
+        path: ndc_relationship_path_from_names(relationship_names, None, relationships)?,
+        column_name: ndc_models::FieldName::from(column),
+        arguments: BTreeMap::new(),
+        field_path: None,
+    })
+}
+
 /// Creates the appropriate NDC count aggregation based on whether we're selecting
 /// a column (nested or otherwise) or not
 fn ndc_count_aggregate(column_path: &[&str], distinct: bool) -> ndc_models::Aggregate {
@@ -134,16 +278,21 @@ pub(crate) fn plan_query_execution<'s, 'ir>(
     let mut collection_relationships = BTreeMap::new();
     relationships::collect_relationships(ir, &mut collection_relationships)?;
 
-    let (query, join_locations) =
+    let (query, join_locations, order_by_arguments) =
         plan_query_node(ir, &mut collection_relationships, join_id_counter)?;
+    let mut arguments = common::plan_ndc_arguments(
+        &ir.arguments,
+        ir.data_connector.capabilities.supported_ndc_version,
+        &mut collection_relationships,
+    )?;
+    // Root-level relevance order-by targets (no relationship path to carry the
+    // search term on) surface their `fields_{n}`/`search_{n}` as collection
+    // arguments instead; merge those in alongside the model's own arguments.
+    merge_order_by_arguments(&mut arguments, order_by_arguments)?;
     let execution_node = types::QueryExecutionPlan {
         query_node: query,
         collection: ndc_models::CollectionName::from(ir.collection.as_str()),
-        arguments: common::plan_ndc_arguments(
-            &ir.arguments,
-            ir.data_connector.capabilities.supported_ndc_version,
-            &mut collection_relationships,
-        )?,
+        arguments,
         collection_relationships,
         variables: None,
         data_connector: ir.data_connector,
@@ -151,67 +300,433 @@ pub(crate) fn plan_query_execution<'s, 'ir>(
     Ok((execution_node, join_locations))
 }
 
-fn ndc_order_by(order_by_elements: &[order_by::OrderByElement]) -> ndc_models::OrderBy {
-    ndc_models::OrderBy {
-        elements: order_by_elements
-            .iter()
-            .map(|element| ndc_models::OrderByElement {
+/// Merges collection-level arguments generated while planning an order-by
+/// (see `OrderByArguments`) into a model's own NDC arguments. The two maps
+/// must be disjoint - silently overwriting a real model argument with a
+/// generated one would be a hard-to-diagnose correctness bug - so a
+/// collision is surfaced as an error instead.
+fn merge_order_by_arguments(
+    arguments: &mut BTreeMap<ndc_models::ArgumentName, ndc_models::Argument>,
+    order_by_arguments: OrderByArguments,
+) -> Result<(), error::Error> {
+    for (name, argument) in order_by_arguments {
+        if arguments.contains_key(&name) {
+            return Err(error::Error::RelevanceArgumentNameCollision { name });
+        }
+        arguments.insert(name, argument);
+    }
+    Ok(())
+}
+
+fn ndc_order_by(
+    order_by_elements: &[order_by::OrderByElement],
+    relationships: &mut BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+    relevance_argument_counter: &mut MonotonicCounter,
+) -> Result<(ndc_models::OrderBy, OrderByArguments), error::Error> {
+    let mut order_by_arguments = OrderByArguments::new();
+    let elements = order_by_elements
+        .iter()
+        .map(|element| {
+            let (target, arguments) = ndc_order_by_target(
+                &element.target,
+                element.predicate.as_ref(),
+                relationships,
+                relevance_argument_counter,
+            )?;
+            order_by_arguments.extend(arguments);
+            Ok(ndc_models::OrderByElement {
                 order_direction: match element.order_direction {
                     schema::ModelOrderByDirection::Asc => ndc_models::OrderDirection::Asc,
                     schema::ModelOrderByDirection::Desc => ndc_models::OrderDirection::Desc,
                 },
-                target: ndc_order_by_target(&element.target),
+                target,
             })
-            .collect(),
+        })
+        .collect::<Result<Vec<_>, error::Error>>()?;
+    Ok((ndc_models::OrderBy { elements }, order_by_arguments))
+}
+
+/// Builds the NDC relationship path segments needed to reach a column (or
+/// dimension, or aggregate) through zero or more relationships.
+///
+/// When using a nested relationship column, you'll have to provide all the relationships(paths)
+/// NDC has to traverse to access the column. The ordering of that paths is important.
+/// The order decides how to access the column.
+///
+/// For example, if you have a model called `User` with a relationship column called `Posts`
+/// which has a relationship column called `Comments` which has a non-relationship column
+/// called `text`, you'll have to provide the following paths to access the `text` column:
+/// ["UserPosts", "PostsComments"]
+///
+/// `predicate`, when present, restricts the *last* relationship hop to only
+/// the matching related rows before they're used as the order-by target, so
+/// that only that subset of related rows (e.g. a user's published posts)
+/// feeds the ordering. Earlier hops are plain navigational
+/// joins and keep the always-true `And` predicate.
+///
+/// A predicate has nowhere to attach when `relationship_path` is empty (a
+/// root-level column, e.g. "order users so that active ones come first");
+/// there's no NDC representation for a partial sort of the root table, so
+/// this is rejected outright rather than silently ignored.
+fn ndc_relationship_path(
+    relationship_path: &[order_by::RelationshipPathElement],
+    predicate: Option<&filter::FilterExpression>,
+    relationships: &mut BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+) -> Result<Vec<ndc_models::PathElement>, error::Error> {
+    let relationship_names = relationship_path.iter().map(|path| path.0.as_str());
+    ndc_relationship_path_from_names(relationship_names, predicate, relationships)
+}
+
+/// The primitive core of `ndc_relationship_path`, taking plain relationship
+/// names instead of `order_by::RelationshipPathElement` so it can be unit
+/// tested without depending on that type's (externally defined) shape.
+fn ndc_relationship_path_from_names<'a>(
+    relationship_names: impl ExactSizeIterator<Item = &'a str>,
+    predicate: Option<&filter::FilterExpression>,
+    relationships: &mut BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+) -> Result<Vec<ndc_models::PathElement>, error::Error> {
+    if relationship_names.len() == 0 {
+        return if predicate.is_some() {
+            Err(error::Error::OrderByPredicateRequiresRelationshipPath)
+        } else {
+            Ok(Vec::new())
+        };
     }
+
+    let last_index = relationship_names.len() - 1;
+    relationship_names
+        .enumerate()
+        .map(|(index, relationship_name)| {
+            let path_predicate = if index == last_index {
+                predicate
+                    .map(|predicate| filter::plan_filter_expression(predicate, relationships))
+                    .transpose()?
+                    .flatten()
+            } else {
+                None
+            };
+            Ok(ndc_models::PathElement {
+                relationship: ndc_models::RelationshipName::from(relationship_name),
+                arguments: BTreeMap::new(),
+                predicate: Some(Box::new(path_predicate.unwrap_or(
+                    // An always-true 'AND' predicate indicates that the column can be
+                    // accessed by joining all the relationships paths provided, without
+                    // restricting which related rows participate in the join.
+                    ndc_models::Expression::And {
+                        expressions: Vec::new(),
+                    },
+                ))),
+            })
+        })
+        .collect()
 }
 
-fn ndc_order_by_target(target: &order_by::OrderByTarget) -> ndc_models::OrderByTarget {
+/// Translates an order-by target into its NDC representation, alongside any
+/// collection-level arguments it needs that have no relationship path to
+/// attach to (see the `Relevance` arm below).
+fn ndc_order_by_target(
+    target: &order_by::OrderByTarget,
+    predicate: Option<&filter::FilterExpression>,
+    relationships: &mut BTreeMap<ndc_models::RelationshipName, ndc_models::Relationship>,
+    relevance_argument_counter: &mut MonotonicCounter,
+) -> Result<(ndc_models::OrderByTarget, OrderByArguments), error::Error> {
     match target {
         order_by::OrderByTarget::Column {
             name,
             relationship_path,
-        } => {
-            let mut order_by_element_path = Vec::new();
-            // When using a nested relationship column, you'll have to provide all the relationships(paths)
-            // NDC has to traverse to access the column. The ordering of that paths is important.
-            // The order decides how to access the column.
-            //
-            // For example, if you have a model called `User` with a relationship column called `Posts`
-            // which has a relationship column called `Comments` which has a non-relationship column
-            // called `text`, you'll have to provide the following paths to access the `text` column:
-            // ["UserPosts", "PostsComments"]
-            for path in relationship_path {
-                order_by_element_path.push(ndc_models::PathElement {
-                    relationship: ndc_models::RelationshipName::from(path.0.as_str()),
-                    arguments: BTreeMap::new(),
-                    // 'AND' predicate indicates that the column can be accessed
-                    // by joining all the relationships paths provided
-                    predicate: Some(Box::new(ndc_models::Expression::And {
-                        // TODO(naveen): Add expressions here, when we support sorting with predicates.
-                        //
-                        // There are two types of sorting:
-                        //     1. Sorting without predicates
-                        //     2. Sorting with predicates
-                        //
-                        // In the 1st sort, we sort all the elements of the results either in ascending
-                        // or descing order based on the order_by argument.
-                        //
-                        // In the 2nd sort, we want fetch the entire result but only sort a subset
-                        // of result and put those sorted set either at the beginning or at the end of the
-                        // result.
-                        //
-                        // Currently we only support the 1st type of sort. Hence we don't have any expressions/predicate.
-                        expressions: Vec::new(),
-                    })),
-                });
-            }
-
+        } => Ok((
             ndc_models::OrderByTarget::Column {
                 name: ndc_models::FieldName::from(name.as_str()),
-                path: order_by_element_path,
+                path: ndc_relationship_path(relationship_path, predicate, relationships)?,
                 field_path: None,
+            },
+            OrderByArguments::new(),
+        )),
+        // Ordering by an aggregate computed over a related collection, e.g.
+        // sorting authors by their number of posts, or by `MAX(post.rating)`.
+        order_by::OrderByTarget::Aggregate {
+            relationship_path,
+            aggregate,
+        } => Ok((
+            ndc_models::OrderByTarget::Aggregate {
+                path: ndc_relationship_path(relationship_path, predicate, relationships)?,
+                aggregate: ndc_aggregate(aggregate),
+            },
+            OrderByArguments::new(),
+        )),
+        // Ordering by full-text relevance (`_relevance`-style scoring) against
+        // one or more columns, e.g. "order articles by text-search relevance
+        // against `title,body` for 'rust', descending". There's no dedicated
+        // NDC order-by target for this, so the scored columns and the search
+        // term are carried as `fields`/`search` arguments instead - see the
+        // "Relevance ordering's wire shape is unverified" limitation in the
+        // module docs, this is our best guess at the wire format, not a
+        // confirmed NDC convention - on the trailing path element when there's
+        // a relationship to traverse, or, for the headline single-table case
+        // where `relationship_path` is empty, as collection-level arguments
+        // (named `fields_{n}`/`search_{n}`, see below) merged in by
+        // `plan_query_execution`.
+        //
+        // Unlike the trailing path element (which is private to this order-by
+        // target), the collection-level arguments map is shared by every
+        // order-by element in the query, so the no-path case suffixes the
+        // argument names with `relevance_argument_counter` to keep multiple
+        // root-level relevance targets from clobbering each other's values.
+        order_by::OrderByTarget::Relevance {
+            fields,
+            search,
+            relationship_path,
+        } => {
+            let mut path = ndc_relationship_path(relationship_path, predicate, relationships)?;
+            let fields_value = serde_json::Value::Array(
+                fields
+                    .iter()
+                    .map(|field| serde_json::Value::String(field.to_string()))
+                    .collect(),
+            );
+            let search_value = serde_json::Value::String(search.to_string());
+
+            let order_by_arguments = match path.last_mut() {
+                Some(trailing_path_element) => {
+                    trailing_path_element.arguments.insert(
+                        ndc_models::ArgumentName::from("fields"),
+                        ndc_models::RelationshipArgument::Literal { value: fields_value },
+                    );
+                    trailing_path_element.arguments.insert(
+                        ndc_models::ArgumentName::from("search"),
+                        ndc_models::RelationshipArgument::Literal { value: search_value },
+                    );
+                    OrderByArguments::new()
+                }
+                None => {
+                    let n = relevance_argument_counter.get_next();
+                    let mut order_by_arguments = OrderByArguments::new();
+                    order_by_arguments.insert(
+                        ndc_models::ArgumentName::from(format!("fields_{n}").as_str()),
+                        ndc_models::Argument::Literal { value: fields_value },
+                    );
+                    order_by_arguments.insert(
+                        ndc_models::ArgumentName::from(format!("search_{n}").as_str()),
+                        ndc_models::Argument::Literal { value: search_value },
+                    );
+                    order_by_arguments
+                }
+            };
+
+            Ok((
+                ndc_models::OrderByTarget::Column {
+                    name: ndc_models::FieldName::from("_relevance"),
+                    path,
+                    field_path: None,
+                },
+                order_by_arguments,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ndc_count_aggregate_on_root_column_is_a_star_count() {
+        assert!(matches!(
+            ndc_count_aggregate(&[], false),
+            ndc_models::Aggregate::StarCount {}
+        ));
+    }
+
+    #[test]
+    fn ndc_count_aggregate_on_nested_column_carries_field_path() {
+        let aggregate = ndc_count_aggregate(&["address", "city"], true);
+
+        match aggregate {
+            ndc_models::Aggregate::ColumnCount {
+                column,
+                field_path,
+                distinct,
+            } => {
+                assert_eq!(column, ndc_models::FieldName::from("address"));
+                assert_eq!(field_path, Some(vec![ndc_models::FieldName::from("city")]));
+                assert!(distinct);
+            }
+            other => panic!("expected a ColumnCount aggregate, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn ndc_relationship_path_with_no_path_and_no_predicate_is_empty() {
+        let mut relationships = BTreeMap::new();
+        let path = ndc_relationship_path(&[], None, &mut relationships).unwrap();
+        assert!(path.is_empty());
+    }
+
+    /// Regression test for the documented "known limitation": a partial-sort
+    /// predicate has nowhere to attach when there's no relationship path, so
+    /// it must be rejected rather than silently dropped.
+    #[test]
+    fn ndc_relationship_path_rejects_root_level_predicate() {
+        let mut relationships = BTreeMap::new();
+        let predicate = filter::FilterExpression::LocalFieldComparison {
+            column: "active",
+            operator: "_eq",
+            value: serde_json::Value::Bool(true),
+        };
+
+        let result = ndc_relationship_path(&[], Some(&predicate), &mut relationships);
+
+        assert!(matches!(
+            result,
+            Err(error::Error::OrderByPredicateRequiresRelationshipPath)
+        ));
+    }
+
+    /// Regression test for the headline use case this request describes:
+    /// "order articles by text-search relevance against `title,body` for
+    /// 'rust'" with no relationship path. Earlier drafts of this order-by
+    /// target only attached `fields`/`search` to the trailing relationship
+    /// path element, so a root-level target (no path at all) silently
+    /// dropped them and emitted a bare, search-less `_relevance` column.
+    #[test]
+    fn root_level_relevance_order_by_carries_fields_and_search_as_collection_arguments() {
+        let mut relationships = BTreeMap::new();
+        let mut counter = MonotonicCounter::new();
+        let target = order_by::OrderByTarget::Relevance {
+            fields: vec!["title", "body"],
+            search: "rust",
+            relationship_path: Vec::new(),
+        };
+
+        let (ndc_target, arguments) =
+            ndc_order_by_target(&target, None, &mut relationships, &mut counter).unwrap();
+
+        assert!(matches!(
+            ndc_target,
+            ndc_models::OrderByTarget::Column { path, .. } if path.is_empty()
+        ));
+        assert!(!arguments.is_empty(), "fields/search must not be dropped");
+        assert!(arguments.contains_key(&ndc_models::ArgumentName::from("fields_0")));
+        assert!(arguments.contains_key(&ndc_models::ArgumentName::from("search_0")));
+    }
+
+    /// Happy-path counterpart to
+    /// `ndc_relationship_path_rejects_root_level_predicate`: with a
+    /// relationship path present, the predicate must land on the trailing
+    /// `PathElement` rather than being dropped or applied to every hop.
+    #[test]
+    fn ndc_relationship_path_attaches_predicate_to_trailing_path_element_only() {
+        let mut relationships = BTreeMap::new();
+        let predicate = filter::FilterExpression::LocalFieldComparison {
+            column: "active",
+            operator: "_eq",
+            value: serde_json::Value::Bool(true),
+        };
+
+        let path = ndc_relationship_path_from_names(
+            ["UserPosts", "PostsComments"].into_iter(),
+            Some(&predicate),
+            &mut relationships,
+        )
+        .unwrap();
+
+        assert_eq!(path.len(), 2);
+        assert!(matches!(
+            path[0].predicate.as_deref(),
+            Some(ndc_models::Expression::And { expressions }) if expressions.is_empty()
+        ));
+        assert!(matches!(
+            path[1].predicate.as_deref(),
+            Some(ndc_models::Expression::BinaryComparisonOperator { .. })
+        ));
+    }
+
+    #[test]
+    fn ndc_column_dimension_with_no_relationship_path_has_an_empty_path() {
+        let mut relationships = BTreeMap::new();
+
+        let dimension =
+            ndc_column_dimension(std::iter::empty(), "country", &mut relationships).unwrap();
+
+        match dimension {
+            ndc_models::Dimension::Column {
+                path, column_name, ..
+            } => {
+                assert!(path.is_empty());
+                assert_eq!(column_name, ndc_models::FieldName::from("country"));
+            }
+            other => panic!("expected a Dimension::Column, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn ndc_column_dimension_carries_a_relationship_path() {
+        let mut relationships = BTreeMap::new();
+
+        let dimension =
+            ndc_column_dimension(["UserPosts"].into_iter(), "category", &mut relationships)
+                .unwrap();
+
+        match dimension {
+            ndc_models::Dimension::Column { path, .. } => {
+                assert_eq!(path.len(), 1);
+                assert_eq!(
+                    path[0].relationship,
+                    ndc_models::RelationshipName::from("UserPosts")
+                );
             }
+            other => panic!("expected a Dimension::Column, got {other:?}"),
         }
     }
+
+    #[test]
+    fn merge_order_by_arguments_inserts_non_colliding_arguments() {
+        let mut arguments = BTreeMap::new();
+        arguments.insert(
+            ndc_models::ArgumentName::from("limit"),
+            ndc_models::Argument::Literal {
+                value: serde_json::Value::from(10),
+            },
+        );
+        let mut order_by_arguments = OrderByArguments::new();
+        order_by_arguments.insert(
+            ndc_models::ArgumentName::from("search_0"),
+            ndc_models::Argument::Literal {
+                value: serde_json::Value::String("rust".into()),
+            },
+        );
+
+        merge_order_by_arguments(&mut arguments, order_by_arguments).unwrap();
+
+        assert_eq!(arguments.len(), 2);
+        assert!(arguments.contains_key(&ndc_models::ArgumentName::from("search_0")));
+    }
+
+    /// Regression test: a generated order-by argument must never silently
+    /// clobber a real model argument of the same name.
+    #[test]
+    fn merge_order_by_arguments_rejects_name_collision() {
+        let mut arguments = BTreeMap::new();
+        arguments.insert(
+            ndc_models::ArgumentName::from("search_0"),
+            ndc_models::Argument::Literal {
+                value: serde_json::Value::String("a real argument".into()),
+            },
+        );
+        let mut order_by_arguments = OrderByArguments::new();
+        order_by_arguments.insert(
+            ndc_models::ArgumentName::from("search_0"),
+            ndc_models::Argument::Literal {
+                value: serde_json::Value::String("rust".into()),
+            },
+        );
+
+        let result = merge_order_by_arguments(&mut arguments, order_by_arguments);
+
+        assert!(matches!(
+            result,
+            Err(error::Error::RelevanceArgumentNameCollision { name })
+                if name == ndc_models::ArgumentName::from("search_0")
+        ));
+    }
 }